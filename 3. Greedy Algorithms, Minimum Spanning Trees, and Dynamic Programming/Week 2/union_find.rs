@@ -1,80 +1,289 @@
 /**
  * Union Find Data Structure.
- * Uses union by rank and leverages path-compression for optimizations.
+ * Supports union by rank or union by size (selected at construction via new()
+ * or new_by_size()) and
+ * leverages path-compression for optimizations.
  */
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
 
-// Node for the disjoint set.
+// Which criterion union() uses to decide which root gets attached under the other.
+// Both keep path compression, so amortized bounds are unchanged either way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MergeStrategy {
+    // Attach the shorter tree under the taller one. Default.
+    ByRank,
+    // Attach the smaller population under the larger one.
+    BySize,
+}
+
+// Union Find data structure. Generic over any hashable, cloneable element type.
+// Elements are mapped onto dense integer tags so parent/rank live in flat Vecs.
 #[derive(Debug)]
-struct Node {
-    // Max number of hops from leaf to this node.
-    rank: usize,
-    // Parent of this node.
-    parent: i32,
+struct UnionFind<T: Eq + Hash + Clone> {
+    // Maps each element to its dense tag.
+    tags: HashMap<T, usize>,
+    // Maps a dense tag back to its element.
+    elements: Vec<T>,
+    // Parent tag of each tag. A tag that is its own parent is a root.
+    parent: Vec<usize>,
+    // Max number of hops from leaf to this tag's tree. Ranks are logarithmic in
+    // set size, so a single byte is always enough.
+    rank: Vec<u8>,
+    // Number of elements in the tree rooted at this tag. Only meaningful for roots.
+    size: Vec<usize>,
+    // Number of disjoint sets remaining.
+    set_count: usize,
+    // Which criterion union() uses to pick the attaching root.
+    strategy: MergeStrategy,
 }
 
-impl Node {
-    // Create a new set node.
-    fn new(rank: usize, parent: i32) -> Node {
-        Node { rank, parent }
+impl<T: Eq + Hash + Clone> UnionFind<T> {
+    // Returns an empty union find data structure that merges by rank.
+    fn new() -> UnionFind<T> {
+        UnionFind {
+            tags: HashMap::new(),
+            elements: Vec::new(),
+            parent: Vec::new(),
+            rank: Vec::new(),
+            size: Vec::new(),
+            set_count: 0,
+            strategy: MergeStrategy::ByRank,
+        }
     }
-}
 
-// Union Find data structure.
-#[derive(Debug)]
-struct UnionFind {
-    disjoint_sets: HashMap<i32, Node>,
-}
+    // Returns an empty union find data structure that merges by size. Prefer this
+    // when the workload is dominated by size_of queries, since size already
+    // doubles as the balancing criterion.
+    fn new_by_size() -> UnionFind<T> {
+        UnionFind {
+            strategy: MergeStrategy::BySize,
+            ..UnionFind::new()
+        }
+    }
 
-impl UnionFind {
-    // Returns an empty union find data structure.
-    fn new() -> UnionFind {
+    // Returns an empty union find data structure pre-sized for [capacity] elements,
+    // avoiding reallocation of the tag map and backing Vecs as elements are added.
+    fn with_capacity(capacity: usize) -> UnionFind<T> {
         UnionFind {
-            disjoint_sets: HashMap::new(),
+            tags: HashMap::with_capacity(capacity),
+            elements: Vec::with_capacity(capacity),
+            parent: Vec::with_capacity(capacity),
+            rank: Vec::with_capacity(capacity),
+            size: Vec::with_capacity(capacity),
+            set_count: 0,
+            strategy: MergeStrategy::ByRank,
         }
     }
 
     // Adds an object to union find. New object will have rank 0 and it will be its own parent.
-    fn add(&mut self, item: i32) {
-        self.disjoint_sets.insert(item, Node::new(0, item));
+    fn add(&mut self, item: T) {
+        let tag = self.elements.len();
+        self.elements.push(item.clone());
+        self.parent.push(tag);
+        self.rank.push(0);
+        self.size.push(1);
+        self.tags.insert(item, tag);
+        self.set_count += 1;
+    }
+
+    // Returns the number of disjoint sets remaining.
+    fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    // Returns the number of elements in the set containing [x].
+    fn size_of(&mut self, x: T) -> usize {
+        let root = self.find_tag(self.tags[&x]);
+        self.size[root]
+    }
+
+    // Returns every element whose root matches find(x).
+    fn members(&mut self, x: T) -> Vec<T> {
+        let root = self.find_tag(self.tags[&x]);
+        let matching_tags: Vec<usize> = (0..self.elements.len())
+            .filter(|&tag| self.find_tag(tag) == root)
+            .collect();
+        matching_tags
+            .into_iter()
+            .map(|tag| self.elements[tag].clone())
+            .collect()
     }
 
-    // Do a union by rank of two disjoint sets.
-    fn union(&mut self, x: i32, y: i32) {
-        // Find the parent of both items.
-        let parent_x = self.find(x);
-        let parent_y = self.find(y);
+    // Merges the disjoint sets containing [x] and [y], per self.strategy.
+    fn union(&mut self, x: T, y: T) {
+        let root_x = self.find_tag(self.tags[&x]);
+        let root_y = self.find_tag(self.tags[&y]);
 
         // They already belong to same set.
-        if parent_x == parent_y {
+        if root_x == root_y {
             return;
         }
 
-        // Parent_y has less height that parent_x. Attach y set to x.
-        if self.disjoint_sets.get(&parent_x).unwrap().rank > self.disjoint_sets.get(&parent_y).unwrap().rank {
-            self.disjoint_sets.get_mut(&parent_y).unwrap().parent = parent_x;
+        match self.strategy {
+            MergeStrategy::ByRank => self.union_by_rank(root_x, root_y),
+            MergeStrategy::BySize => self.union_by_size(root_x, root_y),
+        }
+
+        self.set_count -= 1;
+    }
+
+    // Attach the lower-rank root under the higher-rank root. On equal ranks,
+    // attach root_y under root_x and raise root_x's rank.
+    fn union_by_rank(&mut self, root_x: usize, root_y: usize) {
+        // root_y has less height than root_x. Attach y's set to x.
+        if self.rank[root_x] > self.rank[root_y] {
+            self.parent[root_y] = root_x;
+            self.size[root_x] += self.size[root_y];
         }
-        // Parent_x has less height that parent_y.
-        else if self.disjoint_sets.get(&parent_x).unwrap().rank < self.disjoint_sets.get(&parent_y).unwrap().rank {
-            self.disjoint_sets.get_mut(&parent_x).unwrap().parent = parent_y;
+        // root_x has less height than root_y.
+        else if self.rank[root_x] < self.rank[root_y] {
+            self.parent[root_x] = root_y;
+            self.size[root_y] += self.size[root_x];
         }
         // Same height. Adjust ranks.
         else {
-            self.disjoint_sets.get_mut(&parent_y).unwrap().parent = parent_x;
-            self.disjoint_sets.get_mut(&parent_x).unwrap().rank += 1;
+            self.parent[root_y] = root_x;
+            self.rank[root_x] += 1;
+            self.size[root_x] += self.size[root_y];
         }
     }
 
-    // Returns the parent of [x].
-    fn find(&mut self, x: i32) -> i32 {
-        if self.disjoint_sets.get(&x).unwrap().parent != x {
-            // Apply path compression.
-            self.disjoint_sets.get_mut(&x).unwrap().parent = self.find(self.disjoint_sets.get(&x).unwrap().parent);
+    // Attach the smaller-population root under the larger-population root.
+    fn union_by_size(&mut self, root_x: usize, root_y: usize) {
+        if self.size[root_x] >= self.size[root_y] {
+            self.parent[root_y] = root_x;
+            self.size[root_x] += self.size[root_y];
+        } else {
+            self.parent[root_x] = root_y;
+            self.size[root_y] += self.size[root_x];
+        }
+    }
+
+    // Returns the representative element of the set containing [x].
+    fn find(&mut self, x: T) -> T {
+        let tag = self.tags[&x];
+        let root = self.find_tag(tag);
+        self.elements[root].clone()
+    }
+
+    // Returns the root tag of [tag], applying full path compression along the way.
+    // Iterative two-pass approach so arbitrarily deep chains can't blow the stack.
+    fn find_tag(&mut self, tag: usize) -> usize {
+        // First pass: walk up to the root following parent links.
+        let mut root = tag;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // Second pass: walk the same path again, pointing every node directly at root.
+        let mut current = tag;
+        while current != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    // Path-compresses every element so each one's parent points straight at its
+    // root. Call this before walking parent directly; otherwise elements that
+    // were never find()'d since their last union keep stale intermediate tags.
+    fn finalize(&mut self) {
+        for tag in 0..self.elements.len() {
+            self.find_tag(tag);
+        }
+    }
+
+    // Finalizes the forest and returns a stable, contiguous group id per element.
+    fn into_labeling(mut self) -> HashMap<T, usize> {
+        self.finalize();
+        let mut labels = HashMap::with_capacity(self.elements.len());
+        let mut next_label = HashMap::new();
+        for tag in 0..self.elements.len() {
+            let root = self.parent[tag];
+            let next = next_label.len();
+            let label = *next_label.entry(root).or_insert(next);
+            labels.insert(self.elements[tag].clone(), label);
+        }
+        labels
+    }
+
+    // Finalizes the forest and collects elements grouped by representative.
+    fn groups(&mut self) -> Vec<Vec<T>> {
+        self.finalize();
+        let mut groups: HashMap<usize, Vec<T>> = HashMap::new();
+        for tag in 0..self.elements.len() {
+            let root = self.parent[tag];
+            groups.entry(root).or_default().push(self.elements[tag].clone());
+        }
+        groups.into_values().collect()
+    }
+}
+
+// Returns every node mentioned by [edges], each appearing once, in first-seen order.
+fn nodes_from_edges<T: Eq + Hash + Clone>(edges: &[(T, T, i64)]) -> Vec<T> {
+    let mut seen = HashSet::new();
+    let mut nodes = Vec::new();
+    for (u, v, _) in edges {
+        for node in [u, v] {
+            if seen.insert(node.clone()) {
+                nodes.push(node.clone());
+            }
+        }
+    }
+    nodes
+}
+
+// Builds a union-find over every node in [edges] plus the edges sorted by
+// ascending weight, ready for Kruskal-style incremental merging.
+fn prepare_for_kruskal<T: Eq + Hash + Clone>(edges: &[(T, T, i64)]) -> (UnionFind<T>, Vec<&(T, T, i64)>) {
+    let mut union_find = UnionFind::with_capacity(edges.len());
+    for node in nodes_from_edges(edges) {
+        union_find.add(node);
+    }
+
+    let mut sorted_edges: Vec<&(T, T, i64)> = edges.iter().collect();
+    sorted_edges.sort_by_key(|&(_, _, weight)| weight);
+
+    (union_find, sorted_edges)
+}
+
+// Builds a minimum spanning tree over [edges] with Kruskal's algorithm and
+// returns its total weight.
+fn kruskal_mst<T: Eq + Hash + Clone>(edges: &[(T, T, i64)]) -> i64 {
+    let (mut union_find, sorted_edges) = prepare_for_kruskal(edges);
+
+    let mut total_weight = 0;
+    for &(ref u, ref v, weight) in sorted_edges {
+        if union_find.find(u.clone()) != union_find.find(v.clone()) {
+            union_find.union(u.clone(), v.clone());
+            total_weight += weight;
         }
+    }
+
+    total_weight
+}
+
+// Single-linkage k-clustering: merges the closest clusters until exactly [k]
+// remain, then returns the maximum spacing, i.e. the weight of the next
+// cheapest edge that still crosses between two surviving clusters.
+fn max_spacing_clustering<T: Eq + Hash + Clone>(edges: &[(T, T, i64)], k: usize) -> i64 {
+    let (mut union_find, sorted_edges) = prepare_for_kruskal(edges);
 
-        // This is the leader of this disjoint set.
-        return self.disjoint_sets.get(&x).unwrap().parent;
+    for &(ref u, ref v, weight) in sorted_edges {
+        if union_find.find(u.clone()) == union_find.find(v.clone()) {
+            continue;
+        }
+        if union_find.set_count() == k {
+            return weight;
+        }
+        union_find.union(u.clone(), v.clone());
     }
+
+    0
 }
 
 fn main() {
@@ -94,4 +303,195 @@ fn main() {
     println!("Find(5): {}", union_find.find(5));
     println!("Find(6): {}", union_find.find(6));
     println!("Find(1): {}", union_find.find(1));
+
+    println!("Set count: {}", union_find.set_count());
+    println!("Size of 5's set: {}", union_find.size_of(5));
+    println!("Members of 5's set: {:?}", union_find.members(5));
+    println!("Groups: {:?}", union_find.groups());
+
+    let mut by_size = UnionFind::new_by_size();
+    for i in 1..=6 {
+        by_size.add(i);
+    }
+    by_size.union(1, 2);
+    by_size.union(3, 4);
+    by_size.union(1, 3);
+    println!("By-size groups: {:?}", by_size.groups());
+    println!("By-size labeling: {:?}", by_size.into_labeling());
+
+    // Small weighted graph for the Kruskal MST / max-spacing clustering drivers:
+    // a 4-cycle of unit edges plus two expensive diagonals.
+    let edges = vec![
+        (1, 2, 1),
+        (2, 3, 1),
+        (3, 4, 1),
+        (4, 1, 1),
+        (1, 3, 5),
+        (2, 4, 5),
+    ];
+    println!("MST weight: {}", kruskal_mst(&edges));
+    println!(
+        "Max spacing for k=2 clusters: {}",
+        max_spacing_clustering(&edges, 2)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Square with both diagonals: 1-2, 2-3, 3-4, 4-1 weight 1, diagonals weight 5.
+    fn square_with_diagonals() -> Vec<(i32, i32, i64)> {
+        vec![
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 4, 1),
+            (4, 1, 1),
+            (1, 3, 5),
+            (2, 4, 5),
+        ]
+    }
+
+    // Two cheap pairs, {1,2} and {3,4}, linked only by expensive cross edges.
+    fn two_cheap_pairs() -> Vec<(i32, i32, i64)> {
+        vec![
+            (1, 2, 1),
+            (3, 4, 2),
+            (1, 3, 10),
+            (1, 4, 12),
+            (2, 3, 11),
+            (2, 4, 13),
+        ]
+    }
+
+    #[test]
+    fn set_count_size_of_and_members_track_merges() {
+        let mut union_find = UnionFind::new();
+        for i in 1..=5 {
+            union_find.add(i);
+        }
+        assert_eq!(union_find.set_count(), 5);
+
+        union_find.union(1, 2);
+        union_find.union(2, 3);
+        assert_eq!(union_find.set_count(), 3);
+        assert_eq!(union_find.size_of(1), 3);
+        assert_eq!(union_find.size_of(4), 1);
+
+        let mut members = union_find.members(1);
+        members.sort();
+        assert_eq!(members, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn union_by_size_attaches_smaller_population_under_larger() {
+        let mut union_find = UnionFind::new_by_size();
+        for i in 1..=5 {
+            union_find.add(i);
+        }
+
+        // Build a 3-element set {1,2,3}, then merge the 1-element set {4} into
+        // it: exercises the branch where root_x's population is already larger.
+        union_find.union(1, 2);
+        union_find.union(1, 3);
+        union_find.union(1, 4);
+        assert_eq!(union_find.size_of(1), 4);
+        assert_eq!(union_find.find(4), 1);
+
+        // Merge in the 1-element set {5} with the smaller population passed as
+        // the first argument, exercising the opposite attach branch.
+        union_find.union(5, 1);
+        assert_eq!(union_find.find(5), 1);
+        assert_eq!(union_find.size_of(5), 5);
+        assert_eq!(union_find.set_count(), 1);
+    }
+
+    #[test]
+    fn groups_and_into_labeling_partition_after_unions() {
+        let mut union_find = UnionFind::new();
+        for i in 1..=5 {
+            union_find.add(i);
+        }
+        union_find.union(1, 2);
+        union_find.union(3, 4);
+        // 5 stays a singleton.
+
+        let mut groups = union_find.groups();
+        for group in &mut groups {
+            group.sort();
+        }
+        groups.sort();
+        assert_eq!(groups, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+        let labeling = union_find.into_labeling();
+        assert_eq!(labeling[&1], labeling[&2]);
+        assert_eq!(labeling[&3], labeling[&4]);
+        assert_ne!(labeling[&1], labeling[&3]);
+        assert_ne!(labeling[&1], labeling[&5]);
+    }
+
+    #[test]
+    fn generic_union_find_works_with_string_elements() {
+        let mut union_find: UnionFind<String> = UnionFind::new();
+        for city in ["nyc", "boston", "philly", "dc"] {
+            union_find.add(city.to_string());
+        }
+
+        union_find.union("nyc".to_string(), "boston".to_string());
+        union_find.union("philly".to_string(), "dc".to_string());
+
+        assert_eq!(
+            union_find.find("nyc".to_string()),
+            union_find.find("boston".to_string())
+        );
+        assert_ne!(
+            union_find.find("nyc".to_string()),
+            union_find.find("philly".to_string())
+        );
+    }
+
+    #[test]
+    fn find_resolves_a_long_unbalanced_chain_without_stack_overflow() {
+        const N: usize = 50_000;
+        let mut union_find: UnionFind<usize> = UnionFind::with_capacity(N);
+        for i in 0..N {
+            union_find.add(i);
+        }
+
+        // Poke parent directly into a straight N-deep chain 0 -> 1 -> ... -> N-1,
+        // bypassing union()'s rank/size balancing so find_tag actually has to
+        // walk (and then compress) a chain as deep as the ones the large
+        // Stanford input files can build before any compression has happened.
+        for tag in 0..N - 1 {
+            union_find.parent[tag] = tag + 1;
+        }
+
+        assert_eq!(union_find.find(0), N - 1);
+
+        // Every visited node should now point straight at the root.
+        assert!(union_find.parent[..N - 1].iter().all(|&p| p == N - 1));
+    }
+
+    #[test]
+    fn kruskal_mst_sums_cheapest_spanning_edges() {
+        let edges = square_with_diagonals();
+        // Any 3 of the 4 unit edges span all nodes; the diagonals are never needed.
+        assert_eq!(kruskal_mst(&edges), 3);
+    }
+
+    #[test]
+    fn max_spacing_clustering_returns_cheapest_cross_cluster_edge() {
+        let edges = two_cheap_pairs();
+        // Merging down to k=2 clusters yields exactly {1,2} and {3,4}; the
+        // cheapest edge crossing between them is the maximum achievable spacing.
+        assert_eq!(max_spacing_clustering(&edges, 2), 10);
+    }
+
+    #[test]
+    fn max_spacing_clustering_with_no_remaining_cross_edge() {
+        let edges = vec![(1, 2, 1), (3, 4, 3)];
+        // All edges get consumed merging down to k=2 clusters, leaving no
+        // cross-cluster edge to report a spacing for.
+        assert_eq!(max_spacing_clustering(&edges, 2), 0);
+    }
 }